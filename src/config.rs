@@ -0,0 +1,39 @@
+//! Tunables shared by [`Metro::dijkstra`], [`Metro::astar`] and
+//! [`Metro::k_shortest`], so callers can trade travel time against number of
+//! line changes instead of getting a single line-hopping "optimal" answer.
+//!
+//! [`Metro::dijkstra`]: crate::Metro::dijkstra
+//! [`Metro::astar`]: crate::Metro::astar
+//! [`Metro::k_shortest`]: crate::Metro::k_shortest
+
+/// Configuration applied by the routing algorithms when weighing edges.
+///
+/// * `dwell_secs` is added to every edge's travel time, modeling the time
+/// spent boarding/alighting at a station.
+/// * `transfer_penalty_secs` is added on top of `dwell_secs` whenever an
+/// edge switches [`Station::line`], discouraging routes that zig-zag
+/// between lines to save a handful of seconds.
+/// * `max_transfers` caps how many line changes a returned route may make;
+/// candidate edges that would exceed it are pruned during the search.
+///
+/// [`Station::line`]: crate::Station::line
+#[derive(Clone, Debug)]
+pub struct RoutingConfig {
+    pub dwell_secs: usize,
+    pub transfer_penalty_secs: usize,
+    pub max_transfers: usize,
+}
+
+impl Default for RoutingConfig {
+    /// Returns the dwell time previously hardcoded in [`Metro::dijkstra`],
+    /// a two-minute transfer penalty, and no cap on the number of changes.
+    ///
+    /// [`Metro::dijkstra`]: crate::Metro::dijkstra
+    fn default() -> Self {
+        RoutingConfig {
+            dwell_secs: 30,
+            transfer_penalty_secs: 120,
+            max_transfers: usize::MAX,
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! Geographic helpers backing [`Metro::astar`] and [`Metro::nearest_station`]:
+//! haversine distance, the A* heuristic built on top of it, and the R-tree
+//! used for nearest-station lookups.
+//!
+//! [`Metro::astar`]: crate::Metro::astar
+//! [`Metro::nearest_station`]: crate::Metro::nearest_station
+
+use rstar::RTree;
+use rstar::primitives::GeomWithData;
+
+use crate::Station;
+
+const EARTH_RADIUS_METERS: f64 = 6_371_000.0;
+
+/// Returns the great-circle distance (in meters) between two
+/// latitude/longitude points, using the haversine formula.
+///
+/// # Arguments
+/// * `lat1`, `lon1` - the coordinates of the first point.
+/// * `lat2`, `lon2` - the coordinates of the second point.
+pub(crate) fn haversine_distance(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (lat1, lat2) = (lat1.to_radians(), lat2.to_radians());
+    let dlat = lat2 - lat1;
+    let dlon = (lon2 - lon1).to_radians();
+
+    let a = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+
+    EARTH_RADIUS_METERS * 2.0 * a.sqrt().asin()
+}
+
+/// Returns an admissible lower bound (in seconds) on the travel time
+/// between `from` and `goal`, used as the A* priority key.
+///
+/// # Arguments
+/// * `from` - the `Station` the search is currently expanding.
+/// * `goal` - the destination `Station`.
+/// * `max_speed` - the fastest edge speed (in meters per second) observed
+/// anywhere in the network.
+pub(crate) fn heuristic(from: &Station, goal: &Station, max_speed: f64) -> usize {
+    if max_speed <= 0.0 {
+        return 0;
+    }
+
+    let meters = haversine_distance(from.lat, from.lon, goal.lat, goal.lon);
+
+    (meters / max_speed).floor() as usize
+}
+
+/// Builds an R-tree over every `Station`'s coordinates, indexed by its
+/// position in `stations`, for use by [`Metro::nearest_station`].
+///
+/// # Arguments
+/// * `stations` - the `Station`s of the network.
+///
+/// [`Metro::nearest_station`]: crate::Metro::nearest_station
+pub(crate) fn build_rtree(stations: &[Station]) -> RTree<GeomWithData<[f64; 2], usize>> {
+    let points = stations
+        .iter()
+        .enumerate()
+        .map(|(i, station)| GeomWithData::new([station.lat, station.lon], i))
+        .collect();
+
+    RTree::bulk_load(points)
+}
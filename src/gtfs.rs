@@ -0,0 +1,254 @@
+//! Support for building a [`Metro`] from a standard GTFS feed instead of the
+//! bespoke `V`/`E` text format read by [`Metro::new`].
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::geo::build_rtree;
+use crate::timetable::build_timetable;
+use crate::{build_adjacency, build_index, LineId, Metro, Station, StationId, Trip};
+
+#[derive(Deserialize)]
+struct GtfsStop {
+    stop_id: String,
+    stop_name: String,
+    stop_lat: f64,
+    stop_lon: f64,
+}
+
+#[derive(Deserialize)]
+struct GtfsRoute {
+    route_id: String,
+    route_short_name: String,
+}
+
+#[derive(Deserialize)]
+struct GtfsTrip {
+    route_id: String,
+    trip_id: String,
+}
+
+#[derive(Deserialize)]
+struct GtfsStopTime {
+    trip_id: String,
+    arrival_time: String,
+    departure_time: String,
+    stop_id: String,
+    stop_sequence: u32,
+}
+
+#[derive(Deserialize)]
+struct GtfsTransfer {
+    from_stop_id: String,
+    to_stop_id: String,
+    #[serde(default)]
+    min_transfer_time: Option<u32>,
+}
+
+impl Metro {
+    /// Constructs a new `Metro` from a GTFS feed directory.
+    ///
+    /// Reads `stops.txt`, `routes.txt`, `trips.txt` and `stop_times.txt`
+    /// (and `transfers.txt`, if present) and maps them onto the same
+    /// [`Station`]/[`Trip`] model used by [`Metro::new`]: a GTFS `stop_id`
+    /// becomes a `Station::id`, `route_short_name` becomes `Station::line`,
+    /// and consecutive `stop_times` rows of a trip become `Trip` edges whose
+    /// `time` is the difference between successive `departure_time` and
+    /// `arrival_time` fields, and `departures` holds that scheduled
+    /// `departure_time` for use by [`Metro::earliest_arrival`].
+    /// `transfers.txt`'s `min_transfer_time` becomes extra walking edges
+    /// between the transfer's two stops. `Station::state` (terminus) is
+    /// derived from the resulting line topology rather than from GTFS's
+    /// `location_type`/`parent_station`, which encode a different hierarchy:
+    /// a stop with at most one same-line neighbor is treated as a terminus.
+    ///
+    /// # Arguments
+    /// * `dir` - the directory holding the GTFS feed's `.txt` files.
+    ///
+    /// # Example
+    /// Importing a three-stop linear line and routing across it:
+    /// ```
+    /// use std::fs;
+    /// use retro::{Metro, RoutingConfig, StationId};
+    ///
+    /// let dir = std::env::temp_dir().join("retro_doctest_from_gtfs");
+    /// fs::remove_dir_all(&dir).ok();
+    /// fs::create_dir_all(&dir).unwrap();
+    /// fs::write(dir.join("stops.txt"),
+    ///     "stop_id,stop_name,stop_lat,stop_lon\n\
+    ///      A,Alpha,48.0,2.0\nB,Bravo,48.1,2.1\nC,Charlie,48.2,2.2\n").unwrap();
+    /// fs::write(dir.join("routes.txt"), "route_id,route_short_name\nR1,1\n").unwrap();
+    /// fs::write(dir.join("trips.txt"), "route_id,trip_id\nR1,T1\n").unwrap();
+    /// fs::write(dir.join("stop_times.txt"),
+    ///     "trip_id,arrival_time,departure_time,stop_id,stop_sequence\n\
+    ///      T1,08:00:00,08:00:00,A,1\nT1,08:05:00,08:05:00,B,2\nT1,08:10:00,08:10:00,C,3\n").unwrap();
+    ///
+    /// let metro = Metro::from_gtfs(&dir);
+    /// let results = metro.dijkstra(StationId(0), StationId(2), &RoutingConfig::default()).unwrap();
+    ///
+    /// assert_eq!(results.time, (11, 0));
+    /// assert_eq!(results.directions[0], StationId(2));
+    ///
+    /// // The feed only has a forward-direction trip (A -> B -> C), so
+    /// // earliest_arrival can't reuse its departures for the reverse
+    /// // direction: there's no scheduled service running C -> A.
+    /// assert!(metro
+    ///     .earliest_arrival(StationId(2), StationId(0), 28_800, &RoutingConfig::default())
+    ///     .is_none());
+    /// # fs::remove_dir_all(&dir).ok();
+    /// ```
+    ///
+    /// [`Metro::new`]: Metro::new
+    /// [`Metro::earliest_arrival`]: Metro::earliest_arrival
+    pub fn from_gtfs(dir: &Path) -> Self {
+        let stops = read_csv::<GtfsStop>(&dir.join("stops.txt"));
+        let routes = read_csv::<GtfsRoute>(&dir.join("routes.txt"));
+        let gtfs_trips = read_csv::<GtfsTrip>(&dir.join("trips.txt"));
+        let stop_times = read_csv::<GtfsStopTime>(&dir.join("stop_times.txt"));
+
+        let route_names: HashMap<String, String> = routes
+            .into_iter()
+            .map(|route| (route.route_id, route.route_short_name))
+            .collect();
+        let trip_routes: HashMap<String, String> = gtfs_trips
+            .into_iter()
+            .map(|trip| (trip.trip_id, trip.route_id))
+            .collect();
+
+        let stop_index: HashMap<String, usize> = stops
+            .iter()
+            .enumerate()
+            .map(|(i, stop)| (stop.stop_id.clone(), i))
+            .collect();
+
+        let mut stations: Vec<Station> = stops
+            .iter()
+            .map(|stop| Station {
+                id: StationId(stop_index[&stop.stop_id]),
+                line: LineId(String::new()),
+                // Placeholder: GTFS's `location_type`/`parent_station` encode
+                // station-vs-platform hierarchy, not "end of line", and are
+                // routinely absent or only set on stops that never appear in
+                // `stop_times`. The real flag is filled in below, once the
+                // line topology is known.
+                state: false,
+                lat: stop.stop_lat,
+                lon: stop.stop_lon,
+                name: stop.stop_name.clone(),
+            })
+            .collect();
+
+        let mut by_trip: HashMap<&str, Vec<&GtfsStopTime>> = HashMap::new();
+        for stop_time in &stop_times {
+            by_trip
+                .entry(stop_time.trip_id.as_str())
+                .or_default()
+                .push(stop_time);
+        }
+
+        let mut trips: Vec<Trip> = Vec::new();
+        let mut line_neighbors: HashMap<usize, HashSet<usize>> = HashMap::new();
+        for (trip_id, mut times) in by_trip {
+            times.sort_by_key(|stop_time| stop_time.stop_sequence);
+
+            let line = trip_routes
+                .get(trip_id)
+                .and_then(|route_id| route_names.get(route_id))
+                .cloned()
+                .unwrap_or_default();
+
+            for window in times.windows(2) {
+                let (prev, next) = (window[0], window[1]);
+                let first = stop_index[&prev.stop_id];
+                let second = stop_index[&next.stop_id];
+                let departure = parse_gtfs_time(&prev.departure_time);
+                let time = parse_gtfs_time(&next.arrival_time).saturating_sub(departure);
+
+                if stations[first].line.0.is_empty() {
+                    stations[first].line = LineId(line.clone());
+                }
+                if stations[second].line.0.is_empty() {
+                    stations[second].line = LineId(line.clone());
+                }
+
+                if stations[first].line == stations[second].line {
+                    line_neighbors.entry(first).or_default().insert(second);
+                    line_neighbors.entry(second).or_default().insert(first);
+                }
+
+                trips.push(Trip {
+                    first: StationId(first),
+                    second: StationId(second),
+                    time,
+                    departures: vec![departure as u32],
+                    predicted: None,
+                });
+            }
+        }
+
+        // A terminus is a stop with at most one neighbor on its own line:
+        // either the physical end of a line, or an isolated stop that never
+        // appears in `stop_times` (and so has no same-line neighbor at all).
+        for (position, station) in stations.iter_mut().enumerate() {
+            let degree = line_neighbors.get(&position).map_or(0, |neighbors| neighbors.len());
+            station.state = degree <= 1;
+        }
+
+        let transfers_path = dir.join("transfers.txt");
+        if transfers_path.exists() {
+            for transfer in read_csv::<GtfsTransfer>(&transfers_path) {
+                if let (Some(&first), Some(&second)) = (
+                    stop_index.get(&transfer.from_stop_id),
+                    stop_index.get(&transfer.to_stop_id),
+                ) {
+                    if first != second {
+                        trips.push(Trip {
+                            first: StationId(first),
+                            second: StationId(second),
+                            time: transfer.min_transfer_time.unwrap_or(0) as usize,
+                            departures: Vec::new(),
+                            predicted: None,
+                        });
+                    }
+                }
+            }
+        }
+
+        let index = build_index(&stations);
+        let adjacency = build_adjacency(&stations, &trips, &index);
+        let rtree = build_rtree(&stations);
+        let timetable = build_timetable(&stations, &trips, &index);
+
+        Metro { stations, trips, adjacency, rtree, timetable, index }
+    }
+}
+
+/// Deserializes every record of a GTFS CSV file into `T`.
+///
+/// # Arguments
+/// * `path` - the path to the GTFS `.txt` file to read.
+fn read_csv<T: for<'de> Deserialize<'de>>(path: &Path) -> Vec<T> {
+    let mut reader = csv::Reader::from_path(path)
+        .unwrap_or_else(|_| panic!("Could not read GTFS file {}", path.display()));
+
+    reader
+        .deserialize()
+        .map(|record| record.expect("Could not parse GTFS record"))
+        .collect()
+}
+
+/// Parses a GTFS `HH:MM:SS` timestamp (hours may exceed 24) into seconds
+/// since midnight.
+///
+/// # Arguments
+/// * `time` - the GTFS timestamp to parse.
+fn parse_gtfs_time(time: &str) -> usize {
+    let parts: Vec<usize> = time
+        .splitn(3, ':')
+        .map(|part| part.parse().expect("Could not parse GTFS time"))
+        .collect();
+
+    parts[0] * 3600 + parts[1] * 60 + parts[2]
+}
@@ -0,0 +1,40 @@
+//! Typed identifiers decoupling a [`Station`]'s external identity from its
+//! position in [`Metro::stations`].
+//!
+//! [`Station`]: crate::Station
+//! [`Metro::stations`]: crate::Metro::stations
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// A typed identifier for a [`Station`].
+///
+/// Several sources of station ids (GTFS feeds, or the bespoke `V`/`E` format
+/// with non-contiguous ids) don't guarantee that a station's external
+/// identifier matches its index in [`Metro::stations`]. Wrapping the raw
+/// `usize` makes that distinction explicit at the type level, so lookups go
+/// through `Metro`'s internal `StationId` -> index map instead of indexing
+/// `stations` directly.
+///
+/// [`Station`]: crate::Station
+/// [`Metro::stations`]: crate::Metro::stations
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct StationId(pub usize);
+
+impl fmt::Display for StationId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A typed identifier for a metro line, wrapping its external name (the
+/// bespoke format's numeric line token, or a GTFS `route_short_name`).
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct LineId(pub String);
+
+impl fmt::Display for LineId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
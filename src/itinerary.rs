@@ -0,0 +1,186 @@
+//! JSON itinerary output for a computed [`Results`], for consumption by
+//! another program or a map frontend instead of [`Metro::print_travel`]'s
+//! ANSI terminal text.
+//!
+//! [`Results`]: crate::Results
+//! [`Metro::print_travel`]: crate::Metro::print_travel
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::{LineId, Metro, Results, StationId};
+
+/// One leg of an itinerary: a ride on a single metro line between two
+/// consecutive changes.
+#[derive(Serialize)]
+pub struct Leg {
+    pub line: LineId,
+    pub board: StationId,
+    pub board_name: String,
+    pub alight: StationId,
+    pub alight_name: String,
+    pub direction: StationId,
+    pub duration_secs: usize,
+}
+
+/// The full leg-by-leg itinerary for a [`Results`], as returned by
+/// [`Metro::itinerary_json`].
+///
+/// [`Results`]: crate::Results
+/// [`Metro::itinerary_json`]: crate::Metro::itinerary_json
+#[derive(Serialize)]
+pub struct Itinerary {
+    pub start: StationId,
+    pub end: StationId,
+    pub time_secs: usize,
+    pub legs: Vec<Leg>,
+}
+
+/// A GeoJSON `LineString` geometry, as embedded in the `Feature` returned by
+/// [`Metro::itinerary_geojson`].
+///
+/// [`Metro::itinerary_geojson`]: crate::Metro::itinerary_geojson
+#[derive(Serialize)]
+struct GeoLineString {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    coordinates: Vec<[f64; 2]>,
+}
+
+/// A GeoJSON `Feature` wrapping a [`GeoLineString`], as returned by
+/// [`Metro::itinerary_geojson`].
+///
+/// [`Metro::itinerary_geojson`]: crate::Metro::itinerary_geojson
+#[derive(Serialize)]
+struct GeoFeature {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    properties: BTreeMap<String, String>,
+    geometry: GeoLineString,
+}
+
+impl Metro {
+    /// Builds the leg-by-leg itinerary for `results` as a JSON string.
+    ///
+    /// # Arguments
+    /// * `&self` - a reference to self.
+    /// * `results` - the `Results` to describe.
+    ///
+    /// # Example
+    /// ```
+    /// use std::fs;
+    /// use retro::{Metro, RoutingConfig, StationId};
+    ///
+    /// let path = std::env::temp_dir().join("retro_doctest_itinerary_json.txt");
+    /// fs::write(&path, "V 0000 1 1 48.860000 2.340000 Alpha\n\
+    ///     V 0001 1 0 48.861000 2.341000 Bravo\n\
+    ///     V 0002 1 1 48.862000 2.342000 Charlie\n\
+    ///     E 0000 0001 300\nE 0001 0002 300\n").unwrap();
+    ///
+    /// let filename = path.to_string_lossy().into_owned();
+    /// let metro = Metro::new(&filename);
+    /// let results = metro.dijkstra(StationId(0), StationId(2), &RoutingConfig::default()).unwrap();
+    ///
+    /// assert_eq!(
+    ///     metro.itinerary_json(&results),
+    ///     "{\"start\":0,\"end\":2,\"time_secs\":660,\"legs\":[{\"line\":\"1\",\"board\":0,\
+    ///      \"board_name\":\"Alpha\",\"alight\":2,\"alight_name\":\"Charlie\",\"direction\":2,\
+    ///      \"duration_secs\":660}]}",
+    /// );
+    /// # fs::remove_file(&path).ok();
+    /// ```
+    pub fn itinerary_json(&self, results: &Results) -> String {
+        let itinerary = self.build_itinerary(results);
+
+        serde_json::to_string(&itinerary).expect("Could not serialize itinerary")
+    }
+
+    /// Builds the itinerary's route geometry as a GeoJSON `Feature` wrapping
+    /// a `LineString`, so it can be rendered directly on a map.
+    ///
+    /// # Arguments
+    /// * `&self` - a reference to self.
+    /// * `results` - the `Results` to describe.
+    ///
+    /// # Example
+    /// ```
+    /// use std::fs;
+    /// use retro::{Metro, RoutingConfig, StationId};
+    ///
+    /// let path = std::env::temp_dir().join("retro_doctest_itinerary_geojson.txt");
+    /// fs::write(&path, "V 0000 1 1 48.860000 2.340000 Alpha\n\
+    ///     V 0001 1 0 48.861000 2.341000 Bravo\n\
+    ///     V 0002 1 1 48.862000 2.342000 Charlie\n\
+    ///     E 0000 0001 300\nE 0001 0002 300\n").unwrap();
+    ///
+    /// let filename = path.to_string_lossy().into_owned();
+    /// let metro = Metro::new(&filename);
+    /// let results = metro.dijkstra(StationId(0), StationId(2), &RoutingConfig::default()).unwrap();
+    ///
+    /// assert_eq!(
+    ///     metro.itinerary_geojson(&results),
+    ///     "{\"type\":\"Feature\",\"properties\":{},\"geometry\":{\"type\":\"LineString\",\
+    ///      \"coordinates\":[[2.34,48.86],[2.342,48.862]]}}",
+    /// );
+    /// # fs::remove_file(&path).ok();
+    /// ```
+    pub fn itinerary_geojson(&self, results: &Results) -> String {
+        let mut stops = vec![&self.stations[self.index_of(results.start)]];
+        stops.extend(results.changes.iter().copied());
+        stops.push(&self.stations[self.index_of(results.end)]);
+
+        let coordinates = stops
+            .iter()
+            .map(|station| [station.lon, station.lat])
+            .collect();
+
+        let feature = GeoFeature {
+            kind: "Feature",
+            properties: BTreeMap::new(),
+            geometry: GeoLineString {
+                kind: "LineString",
+                coordinates,
+            },
+        };
+
+        serde_json::to_string(&feature).expect("Could not serialize itinerary geometry")
+    }
+
+    /// Zips `results`' boarding/alighting stations, directions, and leg
+    /// durations into a leg-by-leg [`Itinerary`].
+    ///
+    /// # Arguments
+    /// * `&self` - a reference to self.
+    /// * `results` - the `Results` to describe.
+    fn build_itinerary(&self, results: &Results) -> Itinerary {
+        let mut boarded = vec![&self.stations[self.index_of(results.start)]];
+        boarded.extend(results.changes.iter().copied());
+
+        let mut alighted = results.changes.clone();
+        alighted.push(&self.stations[self.index_of(results.end)]);
+
+        let legs = boarded
+            .iter()
+            .zip(alighted.iter())
+            .zip(results.directions.iter())
+            .zip(results.leg_durations.iter())
+            .map(|(((board, alight), &direction), &duration_secs)| Leg {
+                line: board.line.clone(),
+                board: board.id,
+                board_name: board.name.clone(),
+                alight: alight.id,
+                alight_name: alight.name.clone(),
+                direction,
+                duration_secs,
+            })
+            .collect();
+
+        Itinerary {
+            start: results.start,
+            end: results.end,
+            time_secs: results.time.0 * 60 + results.time.1,
+            legs,
+        }
+    }
+}
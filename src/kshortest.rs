@@ -0,0 +1,273 @@
+//! [`Metro::k_shortest`]: alternative itineraries via Yen's algorithm, so
+//! callers aren't limited to the single route [`Metro::dijkstra`] settles on.
+//!
+//! [`Metro::k_shortest`]: crate::Metro::k_shortest
+//! [`Metro::dijkstra`]: crate::Metro::dijkstra
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet};
+
+use crate::{get_time, Metro, Results, RoutingConfig, StationId};
+
+impl Metro {
+    /// Returns up to `k` distinct itineraries between `start` and `end`,
+    /// cheapest first, computed with Yen's algorithm: the first result is
+    /// the plain shortest path; each subsequent one is the cheapest path
+    /// that deviates from an already-accepted path at some "spur" node,
+    /// with that path's own previously-used edges removed so the deviation
+    /// can't just retrace it.
+    ///
+    /// # Arguments
+    /// * `&self` - a reference to self.
+    /// * `start` - the identifier of the starting `Station`.
+    /// * `end` - the identifier of the ending `Station`.
+    /// * `k` - the maximum number of itineraries to return.
+    /// * `config` - the dwell time, transfer penalty and transfer cap to
+    /// apply while weighing edges.
+    ///
+    /// # Example
+    /// A diamond network with a cheap `A-B-D` route and a pricier `A-C-D`
+    /// detour:
+    /// ```
+    /// use std::fs;
+    /// use retro::{Metro, RoutingConfig, StationId};
+    ///
+    /// let path = std::env::temp_dir().join("retro_doctest_k_shortest.txt");
+    /// fs::write(&path, "V 0000 1 1 48.000000 2.000000 A\n\
+    ///     V 0001 1 0 48.100000 2.100000 B\n\
+    ///     V 0002 1 0 48.200000 2.200000 C\n\
+    ///     V 0003 1 1 48.300000 2.300000 D\n\
+    ///     E 0000 0001 100\nE 0001 0003 100\n\
+    ///     E 0000 0002 150\nE 0002 0003 150\n").unwrap();
+    ///
+    /// let filename = path.to_string_lossy().into_owned();
+    /// let metro = Metro::new(&filename);
+    /// let routes = metro.k_shortest(StationId(0), StationId(3), 2, &RoutingConfig::default());
+    ///
+    /// assert_eq!(routes.len(), 2);
+    /// assert_eq!(routes[0].time, (4, 20));
+    /// assert_eq!(routes[1].time, (6, 0));
+    /// # fs::remove_file(&path).ok();
+    /// ```
+    pub fn k_shortest(
+        &self,
+        start: StationId,
+        end: StationId,
+        k: usize,
+        config: &RoutingConfig,
+    ) -> Vec<Results> {
+        let start = self.index_of(start);
+        let end = self.index_of(end);
+
+        if k == 0 {
+            return Vec::new();
+        }
+
+        let Some(first) = self.shortest_path_excluding(start, end, 0, config, &HashSet::new(), &HashSet::new()) else {
+            return Vec::new();
+        };
+
+        let mut accepted = vec![first];
+        let mut candidates: BinaryHeap<Reverse<(usize, Vec<usize>)>> = BinaryHeap::new();
+
+        while accepted.len() < k {
+            let previous = accepted.last().unwrap().clone();
+
+            for i in 0..previous.len() - 1 {
+                let spur_node = previous[i];
+                let root_path = &previous[..=i];
+
+                let removed_edges: HashSet<(usize, usize)> = accepted
+                    .iter()
+                    .filter(|path| path.len() > i + 1 && path[..=i] == *root_path)
+                    .map(|path| (path[i], path[i + 1]))
+                    .collect();
+                let removed_nodes: HashSet<usize> = root_path[..i].iter().copied().collect();
+
+                let Some(spur_path) = self.shortest_path_excluding(
+                    spur_node,
+                    end,
+                    self.path_transfers(root_path),
+                    config,
+                    &removed_nodes,
+                    &removed_edges,
+                ) else {
+                    continue;
+                };
+
+                let mut candidate = root_path[..i].to_vec();
+                candidate.extend(spur_path);
+
+                let already_seen = accepted.contains(&candidate)
+                    || candidates.iter().any(|Reverse((_, path))| *path == candidate);
+                if !already_seen {
+                    let cost = self.path_cost(&candidate, config);
+                    candidates.push(Reverse((cost, candidate)));
+                }
+            }
+
+            match candidates.pop() {
+                Some(Reverse((_, path))) => accepted.push(path),
+                None => break,
+            }
+        }
+
+        accepted
+            .iter()
+            .map(|path| self.path_to_results(path, config))
+            .collect()
+    }
+
+    /// Runs [`Metro::relax`]'s weighing rules over the adjacency list from
+    /// `start` to `end`, skipping `removed_nodes` and `removed_edges`, and
+    /// returns the cheapest remaining path as a `Vec` of station positions.
+    ///
+    /// # Arguments
+    /// * `&self` - a reference to self.
+    /// * `start` - the position in `stations` to start the search from.
+    /// * `end` - the position in `stations` to reach.
+    /// * `start_transfers` - the number of line changes already spent before
+    /// reaching `start`, e.g. by the root path of a spur search in
+    /// [`Metro::k_shortest`], so `config.max_transfers` caps the full
+    /// root+spur candidate and not just the spur segment.
+    /// * `config` - the dwell time, transfer penalty and transfer cap to apply.
+    /// * `removed_nodes` - station positions the search may not pass through.
+    /// * `removed_edges` - `(from, to)` edges the search may not take.
+    ///
+    /// [`Metro::relax`]: Metro::relax
+    /// [`Metro::k_shortest`]: Metro::k_shortest
+    fn shortest_path_excluding(
+        &self,
+        start: usize,
+        end: usize,
+        start_transfers: usize,
+        config: &RoutingConfig,
+        removed_nodes: &HashSet<usize>,
+        removed_edges: &HashSet<(usize, usize)>,
+    ) -> Option<Vec<usize>> {
+        let mut distance = vec![usize::MAX; self.stations.len()];
+        let mut transfers = vec![usize::MAX; self.stations.len()];
+        let mut prevs = vec![usize::MAX; self.stations.len()];
+        let mut heap = BinaryHeap::new();
+
+        distance[start] = 0;
+        transfers[start] = start_transfers;
+        heap.push(Reverse((0, start)));
+
+        while let Some(Reverse((dist, current))) = heap.pop() {
+            if dist > distance[current] {
+                continue;
+            }
+            if current == end {
+                break;
+            }
+
+            for &(neighbor, time) in &self.adjacency[current] {
+                if removed_nodes.contains(&neighbor) || removed_edges.contains(&(current, neighbor)) {
+                    continue;
+                }
+
+                let Some(next) = self.relax(current, neighbor, time, dist, transfers[current], config) else {
+                    continue;
+                };
+                if next.0 < distance[neighbor] {
+                    distance[neighbor] = next.0;
+                    transfers[neighbor] = next.1;
+                    prevs[neighbor] = current;
+                    heap.push(Reverse((next.0, neighbor)));
+                }
+            }
+        }
+
+        if distance[end] == usize::MAX {
+            return None;
+        }
+
+        let mut path = vec![end];
+        while *path.last().unwrap() != start {
+            path.push(prevs[*path.last().unwrap()]);
+        }
+        path.reverse();
+
+        Some(path)
+    }
+
+    /// Returns the number of line changes already made along `path`, the
+    /// same way [`Metro::get_changes`] detects one: by comparing consecutive
+    /// stations' [`Station::line`].
+    ///
+    /// # Arguments
+    /// * `&self` - a reference to self.
+    /// * `path` - the sequence of station positions to count changes along.
+    ///
+    /// [`Metro::get_changes`]: Metro::get_changes
+    /// [`Station::line`]: crate::Station::line
+    fn path_transfers(&self, path: &[usize]) -> usize {
+        path.windows(2)
+            .filter(|window| self.stations[window[0]].line != self.stations[window[1]].line)
+            .count()
+    }
+
+    /// Returns the total weighted cost (in seconds) of following `path`
+    /// under `config`, mirroring [`Metro::relax`]'s edge weights.
+    ///
+    /// # Arguments
+    /// * `&self` - a reference to self.
+    /// * `path` - the sequence of station positions to cost out.
+    /// * `config` - the dwell time, transfer penalty and transfer cap to apply.
+    ///
+    /// [`Metro::relax`]: Metro::relax
+    fn path_cost(&self, path: &[usize], config: &RoutingConfig) -> usize {
+        path.windows(2)
+            .map(|window| {
+                let (from, to) = (window[0], window[1]);
+                let time = self.fastest_edge_time(from, to);
+                let is_transfer = self.stations[from].line != self.stations[to].line;
+                self.weigh_edge(time, is_transfer, config)
+            })
+            .sum()
+    }
+
+    /// Builds a `Results` describing `path`, computing each station's
+    /// elapsed time from `path[0]` so [`Metro::get_changes`] can derive
+    /// leg durations the same way [`Metro::dijkstra`] does.
+    ///
+    /// # Arguments
+    /// * `&self` - a reference to self.
+    /// * `path` - the sequence of station positions making up the itinerary.
+    /// * `config` - the dwell time, transfer penalty and transfer cap to apply.
+    ///
+    /// [`Metro::get_changes`]: Metro::get_changes
+    /// [`Metro::dijkstra`]: Metro::dijkstra
+    fn path_to_results(&self, path: &[usize], config: &RoutingConfig) -> Results {
+        let start = path[0];
+        let end = *path.last().unwrap();
+
+        let mut prevs = vec![usize::MAX; self.stations.len()];
+        let mut elapsed = vec![usize::MAX; self.stations.len()];
+        elapsed[start] = 0;
+
+        for window in path.windows(2) {
+            let (from, to) = (window[0], window[1]);
+            prevs[to] = from;
+
+            let time = self.fastest_edge_time(from, to);
+            let is_transfer = self.stations[from].line != self.stations[to].line;
+            elapsed[to] = elapsed[from] + self.weigh_edge(time, is_transfer, config);
+        }
+
+        let time = get_time(elapsed[end]);
+        let (changes, directions, leg_durations) = self.get_changes(start, end, prevs, &elapsed);
+
+        Results {
+            start: self.stations[start].id,
+            time,
+            changes,
+            directions,
+            leg_durations,
+            end: self.stations[end].id,
+            scheduled_arrival: None,
+            realtime_arrival: None,
+        }
+    }
+}
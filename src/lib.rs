@@ -1,20 +1,44 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
 use std::fs;
 
+use rstar::RTree;
+use rstar::primitives::GeomWithData;
+use serde::{Deserialize, Serialize};
+
+mod config;
+mod geo;
+mod gtfs;
+mod ids;
+mod itinerary;
+mod kshortest;
+mod timetable;
+
+pub use config::RoutingConfig;
+use geo::{build_rtree, haversine_distance, heuristic};
+pub use ids::{LineId, StationId};
+pub use itinerary::{Itinerary, Leg};
+use timetable::{build_timetable, next_departure};
+
 /// A structure that represents a metro station.
 ///
 /// The type `Station` is made up of the followings:
 /// * an `id` that is unique to each station,
 /// * a `line` which indicates the metro line passing by,
 /// * a `state` which is true if the station is a terminus and false otherwise,
+/// * `lat`/`lon` giving the station's geographic coordinates,
 /// * a `name` to print when giving the itinerary.
 ///
 /// The `Station` type is one of the building blocks of the type [`Metro`].
 ///
 /// [`Metro`]: Metro
+#[derive(Serialize, Deserialize)]
 pub struct Station {
-    pub id: usize,
-    pub line: String,
+    pub id: StationId,
+    pub line: LineId,
     pub state: bool,
+    pub lat: f64,
+    pub lon: f64,
     pub name: String,
 }
 
@@ -27,34 +51,46 @@ impl Station {
     /// # Example
     /// Constructing a station for "Les Halles":
     /// ```
-    /// use paris_metro::Station;
+    /// use retro::Station;
     ///
-    /// let description = "V 0042 4 0 Les Halles";
+    /// let description = "V 0042 4 0 48.862610 2.346820 Les Halles";
     ///
     /// let station = Station::new(description);
     ///
-    /// assert_eq!(station.id, 42);
-    /// assert_eq!(station.line, String::from("4"));
+    /// assert_eq!(station.id.0, 42);
+    /// assert_eq!(station.line.0, String::from("4"));
     /// assert_eq!(station.state, false);
+    /// assert_eq!(station.lat, 48.862610);
+    /// assert_eq!(station.lon, 2.346820);
     /// assert_eq!(station.name, String::from("Les Halles"));
     /// ```
     pub fn new(description: &str) -> Self {
         let parsed: Vec<&str> = description
             .clone()
             .trim_start_matches("V ")
-            .splitn(4, " ")
+            .splitn(6, " ")
             .collect();
 
         Station {
-            id: parsed[0]
-                .parse::<usize>()
-                .expect("Could not cast as u32"),
-            line: parsed[1]
-                .trim_start_matches("0")
-                .to_string(),
+            id: StationId(
+                parsed[0]
+                    .parse::<usize>()
+                    .expect("Could not cast as u32")
+            ),
+            line: LineId(
+                parsed[1]
+                    .trim_start_matches("0")
+                    .to_string()
+            ),
             state: parsed[2]
                 .eq("1"),
-            name: parsed[3]
+            lat: parsed[3]
+                .parse::<f64>()
+                .expect("Could not cast as f64"),
+            lon: parsed[4]
+                .parse::<f64>()
+                .expect("Could not cast as f64"),
+            name: parsed[5]
                 .to_string(),
         }
     }
@@ -69,16 +105,24 @@ impl Station {
 /// * `first` is the `id` of one of the `Station`s,
 /// * `second` is the `id` of the other,
 /// * `time` is the time it takes (in seconds) to travel between the two
-/// `Station`s.
+/// `Station`s,
+/// * `departures` is a sorted `Vec` of departure times (seconds since
+/// midnight) scheduled for this edge; empty means the edge has no known
+/// timetable and is assumed to be available whenever it's reached,
+/// * `predicted` is an optional realtime delay (in seconds, possibly
+/// negative) applied on top of the next scheduled departure used.
 ///
 /// The `Trip` type is the other building block of the [`Metro`] type.
 ///
 /// [`Station`]: Station
 /// [`Metro`]: Metro
+#[derive(Serialize, Deserialize)]
 pub struct Trip {
-    pub first: usize,
-    pub second: usize,
+    pub first: StationId,
+    pub second: StationId,
     pub time: usize,
+    pub departures: Vec<u32>,
+    pub predicted: Option<i64>,
 }
 
 impl Trip {
@@ -89,15 +133,17 @@ impl Trip {
     ///
     /// # Example
     /// ```
-    /// use paris_metro::Trip;
+    /// use retro::Trip;
     ///
     /// let description = "E 0042 0069 420";
     ///
     /// let trip = Trip::new(description);
     ///
-    /// assert_eq!(trip.first, 42);
-    /// assert_eq!(trip.second, 69);
+    /// assert_eq!(trip.first.0, 42);
+    /// assert_eq!(trip.second.0, 69);
     /// assert_eq!(trip.time, 420);
+    /// assert!(trip.departures.is_empty());
+    /// assert_eq!(trip.predicted, None);
     /// ```
     pub fn new(config: &str) -> Self {
         let parsed: Vec<&str> = config
@@ -107,15 +153,21 @@ impl Trip {
             .collect();
 
         Trip {
-            first: parsed[0]
-                .parse::<usize>()
-                .expect("Could not cast as u32"),
-            second: parsed[1]
-                .parse::<usize>()
-                .expect("Could not cast as u32"),
+            first: StationId(
+                parsed[0]
+                    .parse::<usize>()
+                    .expect("Could not cast as u32")
+            ),
+            second: StationId(
+                parsed[1]
+                    .parse::<usize>()
+                    .expect("Could not cast as u32")
+            ),
             time: parsed[2]
                 .parse::<usize>()
                 .expect("Could not cast as u32"),
+            departures: Vec::new(),
+            predicted: None,
         }
     }
 }
@@ -127,13 +179,35 @@ impl Trip {
 /// It is made up of the followings:
 /// * `stations` is a list (`Vec`) of all the `Station`s part of the network,
 /// * `trips` is a list (`Vec`) of all the possible trips between
-/// the `Station`s of the network.
+/// the `Station`s of the network,
+/// * `adjacency` is a list (`Vec`) indexed by station position, giving the
+/// `(neighbor, time)` pairs reachable directly from that station.
+///
+/// `adjacency` is built once in [`Metro::new`] so that [`Metro::dijkstra`]
+/// doesn't have to rescan `trips` for every station it settles.
+///
+/// * `rtree` is an R-tree over the `Station`s' coordinates, letting
+/// [`Metro::nearest_station`] look up the closest station to a GPS point.
+/// * `timetable` is a list (`Vec`) indexed by station position, giving the
+/// `(neighbor, trip index)` pairs reachable directly from that station,
+/// used by [`Metro::earliest_arrival`] to relax against `Trip::departures`.
+/// * `index` maps each `Station`'s external [`StationId`] to its position in
+/// `stations`, since the two aren't guaranteed to match (a GTFS feed, or the
+/// bespoke format with non-contiguous ids, can assign ids in any order).
 ///
 /// [`Station`]: Station
 /// [`Trip`]: Trip
+/// [`Metro::new`]: Metro::new
+/// [`Metro::dijkstra`]: Metro::dijkstra
+/// [`Metro::nearest_station`]: Metro::nearest_station
+/// [`Metro::earliest_arrival`]: Metro::earliest_arrival
 pub struct Metro {
     pub stations: Vec<Station>,
     pub trips: Vec<Trip>,
+    adjacency: Vec<Vec<(usize, usize)>>,
+    rtree: RTree<GeomWithData<[f64; 2], usize>>,
+    timetable: Vec<Vec<(usize, usize)>>,
+    index: HashMap<StationId, usize>,
 }
 
 impl Metro {
@@ -158,12 +232,32 @@ impl Metro {
             }
         }
 
+        let index = build_index(&stations);
+        let adjacency = build_adjacency(&stations, &trips, &index);
+        let rtree = build_rtree(&stations);
+        let timetable = build_timetable(&stations, &trips, &index);
+
         Metro {
             stations,
             trips,
+            adjacency,
+            rtree,
+            timetable,
+            index,
         }
     }
 
+    /// Returns the position in `stations` of the `Station` carrying `id`.
+    ///
+    /// # Arguments
+    /// * `&self` - a reference to self.
+    /// * `id` - the external identifier to resolve.
+    fn index_of(&self, id: StationId) -> usize {
+        *self.index
+            .get(&id)
+            .unwrap_or_else(|| panic!("Unknown station id {}", id))
+    }
+
     /// Returns a `Vec` of references of `Station`s with a matching name.
     ///
     /// This method asks a station name for the user to input
@@ -204,65 +298,43 @@ impl Metro {
         matches
     }
 
-    /// Returns a `Vec` of references of `Trip`s for the available routes from
-    /// a given `Station` identifier.
-    ///
-    /// This methods looks for all the possible trips for a given station
-    /// identifier.
-    /// TODO: It also removes trips that are only one way.
-    ///
-    /// # Arguments
-    /// * `&self` - a reference to self.
-    /// * `current` - the identifier of the current station.
-    fn get_paths_to_neighboors(&self, current: usize) -> Vec<&Trip> {
-        let mut paths = Vec::new();
-
-        for path in &self.trips {
-            if (current == path.first) | (current == path.second) {
-                paths.push(path);
-            }
-        }
-
-        paths
-    }
-
     /// Returns the `identifier` to a terminus `Station`.
     ///
     /// From a previous and current station, determines the `Station`
     /// (terminus) at the end of the metro line.
     ///
+    /// Walks `self.adjacency` one station at a time instead of rescanning
+    /// `self.trips`, so each station walked towards the terminus costs one
+    /// adjacency lookup rather than an `O(trips.len())` rescan.
+    ///
     /// # Arguments
     /// * `&self` - a reference to self.
-    /// * `prev` - the identifier of the previous station.
-    /// * `curr` - the identifier of the current station.
-    fn get_terminus(&self, mut prev: usize, mut curr: usize) -> usize {
+    /// * `prev` - the position in `stations` of the previous station.
+    /// * `curr` - the position in `stations` of the current station.
+    fn get_terminus(&self, mut prev: usize, mut curr: usize) -> StationId {
         while self.stations[curr].state == false {
-            let trips = Self::get_paths_to_neighboors(self, curr);
-
-            for trip in trips {
-                if curr == trip.first {
-                    if (&self.stations[curr].line == &self.stations[trip.second].line) &&
-                        (prev != trip.second)
-                    {
-                        prev = curr;
-                        curr = trip.second;
-                    }
-                } else if curr == trip.second {
-                    if (&self.stations[curr].line == &self.stations[trip.first].line) &&
-                       (prev != trip.first)
-                    {
-                        prev = curr;
-                        curr = trip.first;
-                    }
+            let next = self.adjacency[curr]
+                .iter()
+                .find(|&&(neighbor, _)| {
+                    neighbor != prev && self.stations[curr].line == self.stations[neighbor].line
+                })
+                .map(|&(neighbor, _)| neighbor);
+
+            match next {
+                Some(neighbor) => {
+                    prev = curr;
+                    curr = neighbor;
                 }
+                None => break,
             }
         }
 
         self.stations[curr].id
     }
 
-    /// Returns a tuple of `Vec`s, one holding references to `Station`s and
-    /// the other holding `usize`.
+    /// Returns a tuple of `Vec`s: references to the `Station`s where the
+    /// user changes lines, the terminus `StationId` of each leg, and each
+    /// leg's duration in seconds.
     ///
     /// From the list `prevs`, gets the full path from the `end` `Station` to
     /// the `end`.
@@ -270,87 +342,484 @@ impl Metro {
     ///
     /// # Argument
     /// * `&self` - a reference to self.
-    /// * `start` - the identifier of the starting `Station`.
-    /// * `end` - the identifier of the ending `Station`.
-    /// * `prevs` - the `Vec` of previous `Station`s identifiers.
+    /// * `start` - the position in `stations` of the starting station.
+    /// * `end` - the position in `stations` of the ending station.
+    /// * `prevs` - the `Vec` of previous stations' positions in `stations`.
+    /// * `elapsed` - time elapsed (in seconds) since `start` for each
+    /// station position, used to derive each leg's duration.
     fn get_changes(
         &self,
         start: usize,
         end: usize,
-        prevs: Vec<usize>
-    ) -> (Vec<&Station>, Vec<usize>) {
+        prevs: Vec<usize>,
+        elapsed: &[usize],
+    ) -> (Vec<&Station>, Vec<StationId>, Vec<usize>) {
         let mut path = Vec::new();
         let mut changes = Vec::new();
         let mut directions = Vec::new();
+        let mut leg_durations = Vec::new();
         let mut current = end;
 
-        path.push(&self.stations[end]);
+        path.push(end);
         while current != start {
             let next = prevs[current];
-            path.push(&self.stations[next]);
+            path.push(next);
             current = next;
         }
         path.reverse();
 
-        directions.push(Self::get_terminus(self, path[0].id, path[1].id));
+        directions.push(Self::get_terminus(self, path[0], path[1]));
+        let mut leg_start = path[0];
         for i in 1..path.len() {
-            if path[i-1].line != path[i].line {
-                changes.push(path[i]);
-                if i+1 < path.len() {
-                    directions.push(Self::get_terminus(
-                        self,
-                        path[i].id,
-                        path[i+1].id
-                    ));
-                }
+            // Only record an intermediate change: one with a further leg to
+            // ride (and therefore a `directions` entry to go with it). If
+            // `end` itself happens to be modeled on a different line than
+            // its predecessor, there's no leg left to board after it, so
+            // leaving it out of `changes` keeps `changes`/`directions`/
+            // `leg_durations` in lockstep (one entry per leg actually
+            // ridden) instead of a dangling `changes` entry with no
+            // matching `directions`.
+            if i+1 < path.len() && self.stations[path[i-1]].line != self.stations[path[i]].line {
+                changes.push(&self.stations[path[i]]);
+                leg_durations.push(elapsed[path[i]] - elapsed[leg_start]);
+                leg_start = path[i];
+                directions.push(Self::get_terminus(self, path[i], path[i+1]));
             }
         }
+        leg_durations.push(elapsed[end] - elapsed[leg_start]);
 
-        (changes, directions)
+        (changes, directions, leg_durations)
     }
 
     /// Computes the shortest path between two `Station`s and returns
     /// a `Results` structure.
     ///
+    /// Runs over the adjacency list built in [`Metro::new`] using a
+    /// binary heap of `Reverse<(distance, node)>` instead of scanning every
+    /// unvisited station on each iteration. Stale heap entries (a node
+    /// popped with a distance larger than the one already settled for it)
+    /// are simply skipped, which is cheaper than removing them up front.
+    ///
     /// # Arguments
     /// * `&self` - a reference to self.
     /// * `start` - the identifier of the starting `Station`.
     /// * `end` - the identifier of the ending `Station`.
-    pub fn dijkstra(&self, start: usize, end: usize) -> Results {
+    /// * `config` - the dwell time, transfer penalty and transfer cap to
+    /// apply while weighing edges.
+    ///
+    /// Returns `None` if `end` is unreachable from `start` (no path exists,
+    /// or every path would exceed `config.max_transfers`).
+    ///
+    /// [`Metro::new`]: Metro::new
+    pub fn dijkstra(&self, start: StationId, end: StationId, config: &RoutingConfig) -> Option<Results> {
+        let start = self.index_of(start);
+        let end = self.index_of(end);
         let mut distance = vec![usize::MAX; self.stations.len()];
+        let mut transfers = vec![usize::MAX; self.stations.len()];
         let mut prevs = vec![usize::MAX; self.stations.len()];
-        let mut unvisited = vec![0; self.stations.len()];
-        let mut visited = 0;
-        let stop_time = 30;
+        let mut heap = BinaryHeap::new();
+
+        distance[start] = 0;
+        transfers[start] = 0;
+        heap.push(Reverse((0, start)));
+
+        while let Some(Reverse((dist, current))) = heap.pop() {
+            if dist > distance[current] {
+                continue;
+            }
+
+            for &(neighbor, time) in &self.adjacency[current] {
+                let Some(next) = self.relax(current, neighbor, time, dist, transfers[current], config) else {
+                    continue;
+                };
+                if next.0 < distance[neighbor] {
+                    distance[neighbor] = next.0;
+                    transfers[neighbor] = next.1;
+                    prevs[neighbor] = current;
+                    heap.push(Reverse((next.0, neighbor)));
+                }
+            }
+        }
 
-        for i in 0..unvisited.capacity() {
-            unvisited[i] = i;
+        if distance[end] == usize::MAX {
+            return None;
         }
+
+        let time: (usize, usize) = get_time(distance[end]);
+        let (changes, directions, leg_durations) = Self::get_changes(self, start, end, prevs, &distance);
+
+        Some(Results {
+            start: self.stations[start].id,
+            time,
+            changes,
+            directions,
+            leg_durations,
+            end: self.stations[end].id,
+            scheduled_arrival: None,
+            realtime_arrival: None,
+        })
+    }
+
+    /// Computes the shortest path between two `Station`s using A*, and
+    /// returns a `Results` structure.
+    ///
+    /// Uses the same adjacency list and priority-queue shape as
+    /// [`Metro::dijkstra`], but orders the heap by `distance + heuristic`
+    /// instead of `distance` alone. The heuristic is the haversine
+    /// great-circle distance to `end` divided by the fastest edge speed
+    /// observed anywhere in the network, which is a lower bound on the
+    /// remaining travel time and therefore keeps the search admissible
+    /// while pruning it towards the destination.
+    ///
+    /// # Arguments
+    /// * `&self` - a reference to self.
+    /// * `start` - the identifier of the starting `Station`.
+    /// * `end` - the identifier of the ending `Station`.
+    /// * `config` - the dwell time, transfer penalty and transfer cap to
+    /// apply while weighing edges.
+    ///
+    /// Returns `None` if `end` is unreachable from `start` (no path exists,
+    /// or every path would exceed `config.max_transfers`).
+    ///
+    /// # Example
+    /// ```
+    /// use std::fs;
+    /// use retro::{Metro, RoutingConfig, StationId};
+    ///
+    /// let path = std::env::temp_dir().join("retro_doctest_astar.txt");
+    /// fs::write(&path, "V 0000 1 1 48.860000 2.340000 Alpha\n\
+    ///     V 0001 1 0 48.861000 2.341000 Bravo\n\
+    ///     V 0002 1 1 48.862000 2.342000 Charlie\n\
+    ///     E 0000 0001 300\nE 0001 0002 300\n").unwrap();
+    ///
+    /// let filename = path.to_string_lossy().into_owned();
+    /// let metro = Metro::new(&filename);
+    /// let results = metro.astar(StationId(0), StationId(2), &RoutingConfig::default()).unwrap();
+    ///
+    /// assert_eq!(results.time, (11, 0));
+    /// # fs::remove_file(&path).ok();
+    /// ```
+    ///
+    /// [`Metro::dijkstra`]: Metro::dijkstra
+    pub fn astar(&self, start: StationId, end: StationId, config: &RoutingConfig) -> Option<Results> {
+        let start = self.index_of(start);
+        let end = self.index_of(end);
+        let mut distance = vec![usize::MAX; self.stations.len()];
+        let mut transfers = vec![usize::MAX; self.stations.len()];
+        let mut prevs = vec![usize::MAX; self.stations.len()];
+        let max_speed = self.max_edge_speed();
+        let goal = &self.stations[end];
+        let mut heap = BinaryHeap::new();
+
         distance[start] = 0;
+        transfers[start] = 0;
+        heap.push(Reverse((
+            heuristic(&self.stations[start], goal, max_speed),
+            start,
+            0,
+        )));
+
+        while let Some(Reverse((_, current, dist))) = heap.pop() {
+            if dist > distance[current] {
+                continue;
+            }
+            if current == end {
+                break;
+            }
 
-        while visited < self.stations.len() {
-            let current = get_next(&mut distance, &mut unvisited, &mut visited);
-            let paths = Self::get_paths_to_neighboors(self, current);
-
-            for path in paths {
-                if current == path.first {
-                    if distance[current] + path.time < distance[path.second] {
-                        distance[path.second] = distance[current] + path.time + stop_time;
-                        prevs[path.second] = current;
-                    }
-                } else if current == path.second {
-                    if distance[current] + path.time < distance[path.first] {
-                        distance[path.first] = distance[current] + path.time + stop_time;
-                        prevs[path.first] = current;
-                    }
+            for &(neighbor, time) in &self.adjacency[current] {
+                let Some(next) = self.relax(current, neighbor, time, dist, transfers[current], config) else {
+                    continue;
+                };
+                if next.0 < distance[neighbor] {
+                    distance[neighbor] = next.0;
+                    transfers[neighbor] = next.1;
+                    prevs[neighbor] = current;
+                    let priority = next.0 + heuristic(&self.stations[neighbor], goal, max_speed);
+                    heap.push(Reverse((priority, neighbor, next.0)));
                 }
             }
         }
 
+        if distance[end] == usize::MAX {
+            return None;
+        }
+
         let time: (usize, usize) = get_time(distance[end]);
-        let (changes, directions) = Self::get_changes(self, start, end, prevs);
+        let (changes, directions, leg_durations) = Self::get_changes(self, start, end, prevs, &distance);
+
+        Some(Results {
+            start: self.stations[start].id,
+            time,
+            changes,
+            directions,
+            leg_durations,
+            end: self.stations[end].id,
+            scheduled_arrival: None,
+            realtime_arrival: None,
+        })
+    }
+
+    /// Returns the `(distance, transfers)` settled at `neighbor` if the edge
+    /// `current -> neighbor` is relaxed, or `None` if taking it would push
+    /// the number of line changes past `config.max_transfers`.
+    ///
+    /// A transfer is detected the same way [`Metro::get_changes`] detects
+    /// one: by comparing `current` and `neighbor`'s [`Station::line`], since
+    /// each `Station` entry belongs to a single line.
+    ///
+    /// # Arguments
+    /// * `&self` - a reference to self.
+    /// * `current` - the position in `stations` being expanded.
+    /// * `neighbor` - the position in `stations` being relaxed into.
+    /// * `time` - the edge's travel time, from `adjacency`.
+    /// * `dist` - the distance already settled at `current`.
+    /// * `transfers` - the number of line changes already settled at `current`.
+    /// * `config` - the dwell time, transfer penalty and transfer cap to apply.
+    ///
+    /// [`Metro::get_changes`]: Metro::get_changes
+    /// [`Station::line`]: Station::line
+    fn relax(
+        &self,
+        current: usize,
+        neighbor: usize,
+        time: usize,
+        dist: usize,
+        transfers: usize,
+        config: &RoutingConfig,
+    ) -> Option<(usize, usize)> {
+        let is_transfer = self.stations[current].line != self.stations[neighbor].line;
+        let transfers = transfers + is_transfer as usize;
+        if transfers > config.max_transfers {
+            return None;
+        }
+
+        Some((dist + self.weigh_edge(time, is_transfer, config), transfers))
+    }
+
+    /// Returns the weighted cost (in seconds) of an edge of travel time
+    /// `time`, adding `config.dwell_secs` and, if `is_transfer`,
+    /// `config.transfer_penalty_secs` on top. Shared by [`Metro::relax`] and
+    /// [`Metro::path_cost`]/[`Metro::path_to_results`] so the weighing rule
+    /// only has one place to change.
+    ///
+    /// # Arguments
+    /// * `&self` - a reference to self.
+    /// * `time` - the edge's travel time.
+    /// * `is_transfer` - whether the edge crosses onto a different line.
+    /// * `config` - the dwell time and transfer penalty to apply.
+    ///
+    /// [`Metro::relax`]: Metro::relax
+    /// [`Metro::path_cost`]: crate::kshortest
+    /// [`Metro::path_to_results`]: crate::kshortest
+    pub(crate) fn weigh_edge(&self, time: usize, is_transfer: bool, config: &RoutingConfig) -> usize {
+        let penalty = if is_transfer { config.transfer_penalty_secs } else { 0 };
+        time + config.dwell_secs + penalty
+    }
+
+    /// Returns the travel time of the fastest edge between `from` and `to`
+    /// in `adjacency`, resolving the routine case of parallel edges between
+    /// the same pair (one `Trip` per consecutive stop pair per GTFS trip)
+    /// the same way Dijkstra settles them: by their fastest duplicate.
+    ///
+    /// # Arguments
+    /// * `&self` - a reference to self.
+    /// * `from` - the position in `stations` the edge starts at.
+    /// * `to` - the position in `stations` the edge ends at.
+    pub(crate) fn fastest_edge_time(&self, from: usize, to: usize) -> usize {
+        self.adjacency[from]
+            .iter()
+            .filter(|&&(neighbor, _)| neighbor == to)
+            .map(|&(_, time)| time)
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Returns the fastest edge speed (in meters per second) observed over
+    /// every `Trip` in the network, used as the A* heuristic's speed bound.
+    fn max_edge_speed(&self) -> f64 {
+        self.trips
+            .iter()
+            .filter(|trip| trip.time > 0)
+            .map(|trip| {
+                let from = &self.stations[self.index_of(trip.first)];
+                let to = &self.stations[self.index_of(trip.second)];
+                haversine_distance(from.lat, from.lon, to.lat, to.lon) / trip.time as f64
+            })
+            .fold(0.0, f64::max)
+    }
+
+    /// Returns a reference to the `Station` closest to a given GPS point.
+    ///
+    /// Looks the point up in the R-tree built over every `Station`'s
+    /// coordinates, so users can route from arbitrary latitude/longitude
+    /// pairs rather than typing exact station names into [`Metro::get_station`].
+    ///
+    /// # Arguments
+    /// * `&self` - a reference to self.
+    /// * `lat` - the latitude of the point to search from.
+    /// * `lon` - the longitude of the point to search from.
+    ///
+    /// # Example
+    /// ```
+    /// use std::fs;
+    /// use retro::Metro;
+    ///
+    /// let path = std::env::temp_dir().join("retro_doctest_nearest.txt");
+    /// fs::write(&path, "V 0000 1 1 48.860000 2.340000 Alpha\n\
+    ///     V 0001 1 0 48.861000 2.341000 Bravo\n\
+    ///     V 0002 1 1 48.862000 2.342000 Charlie\n").unwrap();
+    ///
+    /// let filename = path.to_string_lossy().into_owned();
+    /// let metro = Metro::new(&filename);
+    /// let nearest = metro.nearest_station(48.8611, 2.3411);
+    ///
+    /// assert_eq!(nearest.name, "Bravo");
+    /// # fs::remove_file(&path).ok();
+    /// ```
+    ///
+    /// [`Metro::get_station`]: Metro::get_station
+    pub fn nearest_station(&self, lat: f64, lon: f64) -> &Station {
+        let nearest = self.rtree
+            .nearest_neighbor(&[lat, lon])
+            .expect("Network has no stations");
 
-        Results { start, time, changes, directions, end }
+        &self.stations[nearest.data]
+    }
+
+    /// Computes the earliest itinerary between two `Station`s departing no
+    /// earlier than `departure_time`, and returns a `Results` structure.
+    ///
+    /// Unlike [`Metro::dijkstra`], `distance` here is an absolute clock time
+    /// (seconds since midnight) rather than an accumulated duration: an edge
+    /// is only relaxed against the next `Trip::departures` entry at or after
+    /// the arrival time already settled at its tail station, which is the
+    /// time-dependent Dijkstra relaxation rule. Edges with no timetable
+    /// (`departures` empty) are treated as always available, matching the
+    /// fixed-duration behaviour of [`Metro::dijkstra`]. Each station's
+    /// realtime arrival is its scheduled arrival plus the `Trip::predicted`
+    /// delay of the edge used to reach it, so the returned `Results` can
+    /// report both a scheduled and a realtime arrival; delays of earlier
+    /// edges on the route are not carried forward. `config`'s dwell time and
+    /// transfer penalty are applied the same way as [`Metro::relax`], via
+    /// [`Metro::weigh_edge`], and `config.max_transfers` prunes candidate
+    /// edges the same way.
+    ///
+    /// # Arguments
+    /// * `&self` - a reference to self.
+    /// * `start` - the identifier of the starting `Station`.
+    /// * `end` - the identifier of the ending `Station`.
+    /// * `departure_time` - the earliest time (seconds since midnight) to
+    /// leave `start` at.
+    /// * `config` - the dwell time, transfer penalty and transfer cap to
+    /// apply while weighing edges.
+    ///
+    /// Returns `None` if `end` is unreachable from `start` departing at or
+    /// after `departure_time` (e.g. the last service of the day has already
+    /// left) — a routine outcome for time-windowed routing, not a corner
+    /// case.
+    ///
+    /// # Example
+    /// Arriving at `HubB`, modeled on a different line than its predecessor
+    /// `HubA` (a transfer hub), still yields exactly one leg duration per
+    /// `directions` entry:
+    /// ```
+    /// use std::fs;
+    /// use retro::{Metro, RoutingConfig, StationId};
+    ///
+    /// let path = std::env::temp_dir().join("retro_doctest_earliest_arrival.txt");
+    /// fs::write(&path, "V 0000 1 1 48.860000 2.340000 Start\n\
+    ///     V 0001 1 0 48.861000 2.341000 HubA\n\
+    ///     V 0002 2 1 48.862000 2.342000 HubB\n\
+    ///     E 0000 0001 300\nE 0001 0002 60\n").unwrap();
+    ///
+    /// let filename = path.to_string_lossy().into_owned();
+    /// let metro = Metro::new(&filename);
+    /// let results = metro
+    ///     .earliest_arrival(StationId(0), StationId(2), 28_800, &RoutingConfig::default())
+    ///     .unwrap();
+    ///
+    /// // HubA -> HubB incurs the default transfer penalty on top of dwell time.
+    /// assert_eq!(results.scheduled_arrival, Some(29_340));
+    /// assert_eq!(results.directions.len(), results.leg_durations.len());
+    /// assert_eq!(results.leg_durations, vec![540]);
+    /// # fs::remove_file(&path).ok();
+    /// ```
+    ///
+    /// [`Metro::dijkstra`]: Metro::dijkstra
+    /// [`Metro::relax`]: Metro::relax
+    /// [`Metro::weigh_edge`]: Metro::weigh_edge
+    pub fn earliest_arrival(
+        &self,
+        start: StationId,
+        end: StationId,
+        departure_time: u32,
+        config: &RoutingConfig,
+    ) -> Option<Results> {
+        let start = self.index_of(start);
+        let end = self.index_of(end);
+        let mut scheduled = vec![u32::MAX; self.stations.len()];
+        let mut realtime = vec![u32::MAX; self.stations.len()];
+        let mut transfers = vec![usize::MAX; self.stations.len()];
+        let mut prevs = vec![usize::MAX; self.stations.len()];
+        let mut heap = BinaryHeap::new();
+
+        scheduled[start] = departure_time;
+        realtime[start] = departure_time;
+        transfers[start] = 0;
+        heap.push(Reverse((departure_time, start)));
+
+        while let Some(Reverse((arrival, current))) = heap.pop() {
+            if arrival > scheduled[current] {
+                continue;
+            }
+
+            for &(neighbor, trip_index) in &self.timetable[current] {
+                let trip = &self.trips[trip_index];
+
+                let Some(departure) = next_departure(&trip.departures, scheduled[current]) else {
+                    continue;
+                };
+
+                let is_transfer = self.stations[current].line != self.stations[neighbor].line;
+                let next_transfers = transfers[current] + is_transfer as usize;
+                if next_transfers > config.max_transfers {
+                    continue;
+                }
+
+                let trip_arrival = departure + self.weigh_edge(trip.time, is_transfer, config) as u32;
+                if trip_arrival < scheduled[neighbor] {
+                    let delay = trip.predicted.unwrap_or(0);
+                    scheduled[neighbor] = trip_arrival;
+                    realtime[neighbor] = (trip_arrival as i64 + delay).max(0) as u32;
+                    transfers[neighbor] = next_transfers;
+                    prevs[neighbor] = current;
+                    heap.push(Reverse((trip_arrival, neighbor)));
+                }
+            }
+        }
+
+        if scheduled[end] == u32::MAX {
+            return None;
+        }
+
+        let time: (usize, usize) = get_time((scheduled[end] - departure_time) as usize);
+        let elapsed: Vec<usize> = scheduled
+            .iter()
+            .map(|&time| time.saturating_sub(departure_time) as usize)
+            .collect();
+        let (changes, directions, leg_durations) = Self::get_changes(self, start, end, prevs, &elapsed);
+
+        Some(Results {
+            start: self.stations[start].id,
+            time,
+            changes,
+            directions,
+            leg_durations,
+            end: self.stations[end].id,
+            scheduled_arrival: Some(scheduled[end]),
+            realtime_arrival: Some(realtime[end]),
+        })
     }
 
     /// Prints the travel to terminal.
@@ -359,29 +828,55 @@ impl Metro {
     /// * `&self` - a reference to self.
     /// * `results` - the structure holding the results of the dijkstra
     /// algorithm.
+    ///
+    /// # Example
+    /// A route that ends on a different line than its last change (`B`'s
+    /// line differs from `C`'s) doesn't panic: `directions` always has one
+    /// more entry than `changes`.
+    /// ```
+    /// use std::fs;
+    /// use retro::{Metro, RoutingConfig, StationId};
+    ///
+    /// let path = std::env::temp_dir().join("retro_doctest_print_travel.txt");
+    /// fs::write(&path, "V 0000 1 1 48.860000 2.340000 S\n\
+    ///     V 0001 1 0 48.861000 2.341000 A\n\
+    ///     V 0002 2 0 48.862000 2.342000 B\n\
+    ///     V 0003 3 1 48.863000 2.343000 C\n\
+    ///     E 0000 0001 300\nE 0001 0002 300\nE 0002 0003 300\n").unwrap();
+    ///
+    /// let filename = path.to_string_lossy().into_owned();
+    /// let metro = Metro::new(&filename);
+    /// let results = metro.dijkstra(StationId(0), StationId(3), &RoutingConfig::default()).unwrap();
+    ///
+    /// metro.print_travel(&results);
+    /// # fs::remove_file(&path).ok();
+    /// ```
     pub fn print_travel(&self, results: &Results) {
         println!("\nTrip time: \x1b[1m{} mins, {} secs\x1b[0m",
             results.time.0,
             results.time.1
         );
 
-        print!("\n\x1b[1m{}\x1b[0m", &self.stations[results.start].name);
+        let start = &self.stations[self.index_of(results.start)];
+        let end = &self.stations[self.index_of(results.end)];
+
+        print!("\n\x1b[1m{}\x1b[0m", start.name);
         println!("\n|\n|");
         print!("\x1b[1m\x1b[32m{}\x1b[0m - \x1b[1m{}\x1b[0m\n|\tTowards {}",
-            &self.stations[results.start].line,
-            &self.stations[results.start].name,
-            &self.stations[results.directions[0]].name
+            start.line,
+            start.name,
+            &self.stations[self.index_of(results.directions[0])].name
         );
         println!("\n|");
         for i in 0..results.changes.len() {
             print!("\x1b[1m\x1b[32m{}\x1b[0m - \x1b[1m{}\x1b[0m\n|\tTowards {}",
                 results.changes[i].line,
                 results.changes[i].name,
-                &self.stations[results.directions[i+1]].name
+                &self.stations[self.index_of(results.directions[i+1])].name
             );
             println!("\n|");
         }
-        println!("\x1b[1m{}\x1b[0m\n", &self.stations[results.end].name);
+        println!("\x1b[1m{}\x1b[0m\n", end.name);
     }
 }
 
@@ -392,48 +887,30 @@ impl Metro {
 /// * `changes` is the `Vec` holding the `Station`s where the user
 /// has to change metro lines,
 /// * `directions` is the `Vec` holding the terminus `Station`s identifiers,
+/// * `leg_durations` holds each leg's duration in seconds, aligned with
+/// `directions` (one entry per ride between two consecutive changes),
 /// * `time` is a tuple holding the time taken in minutes and seconds,
-/// * `end` - the identifier of the ending `Station`.
+/// * `end` - the identifier of the ending `Station`,
+/// * `scheduled_arrival`/`realtime_arrival` hold the absolute arrival clock
+/// time (seconds since midnight), scheduled and realtime respectively, when
+/// the itinerary was computed by [`Metro::earliest_arrival`]; `None` for
+/// [`Metro::dijkstra`] and [`Metro::astar`], which only know a duration.
+///
+/// [`Metro::earliest_arrival`]: Metro::earliest_arrival
+/// [`Metro::dijkstra`]: Metro::dijkstra
+/// [`Metro::astar`]: Metro::astar
+// `changes` borrows from `Metro::stations`, so `Results` can only be
+// serialized, not deserialized back into an owned value.
+#[derive(Serialize)]
 pub struct Results<'a> {
-    pub start: usize,
+    pub start: StationId,
     pub changes: Vec<&'a Station>,
-    pub directions: Vec<usize>,
+    pub directions: Vec<StationId>,
+    pub leg_durations: Vec<usize>,
     pub time: (usize, usize),
-    pub end: usize,
-}
-
-/// Returns a `usize` corresponding to the identifier of the next `Station`.
-///
-/// # Arguments
-/// * `distance` - a mutable reference on a `Vec` holding the distance
-/// in seconds to the other `Station`s.
-/// * `unvisited` - a mutable reference on a `Vec` holding the identifiers
-/// of the unvisited `Station`s.
-/// * `visited` - a mutable reference on the number of visited `Station`s.
-fn get_next(
-    distance: &mut Vec<usize>,
-    unvisited: &mut Vec<usize>,
-    visited: &mut usize
-) -> usize {
-    let mut min = usize::MAX;
-    let mut next = 0;
-
-    for station in &*unvisited {
-        if distance[*station] < min {
-            min = distance[*station];
-            next = *station;
-        }
-    }
-
-    for i in 0..unvisited.len() {
-        if unvisited[i] == next {
-            unvisited.remove(i);
-            break;
-        }
-    }
-    *visited += 1;
-
-    next
+    pub end: StationId,
+    pub scheduled_arrival: Option<u32>,
+    pub realtime_arrival: Option<u32>,
 }
 
 /// Returns a tuple of `usize`s holding the time taken in minutes and seconds.
@@ -446,3 +923,48 @@ fn get_time(time: usize) -> (usize, usize) {
 
     (minutes, seconds)
 }
+
+/// Builds the `StationId` -> position-in-`stations` index shared by every
+/// `Metro` construction path, since a station's external id and its
+/// position in the `stations` `Vec` aren't guaranteed to match.
+///
+/// # Arguments
+/// * `stations` - the `Station`s of the network.
+pub(crate) fn build_index(stations: &[Station]) -> HashMap<StationId, usize> {
+    stations
+        .iter()
+        .enumerate()
+        .map(|(i, station)| (station.id, i))
+        .collect()
+}
+
+/// Builds the adjacency list used by [`Metro::dijkstra`] from a list of
+/// `Station`s and `Trip`s, indexed by station position in `stations`.
+///
+/// Shared between [`Metro::new`] and [`Metro::from_gtfs`] so both
+/// construction paths expose the same routable network.
+///
+/// # Arguments
+/// * `stations` - the `Station`s of the network.
+/// * `trips` - the `Trip`s linking those `Station`s together.
+/// * `index` - the `StationId` -> position map built by [`build_index`].
+///
+/// [`Metro::dijkstra`]: Metro::dijkstra
+/// [`Metro::new`]: Metro::new
+/// [`Metro::from_gtfs`]: Metro::from_gtfs
+pub(crate) fn build_adjacency(
+    stations: &[Station],
+    trips: &[Trip],
+    index: &HashMap<StationId, usize>
+) -> Vec<Vec<(usize, usize)>> {
+    let mut adjacency = vec![Vec::new(); stations.len()];
+
+    for trip in trips {
+        let first = index[&trip.first];
+        let second = index[&trip.second];
+        adjacency[first].push((second, trip.time));
+        adjacency[second].push((first, trip.time));
+    }
+
+    adjacency
+}
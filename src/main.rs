@@ -1,6 +1,8 @@
 use retro::*;
 
 fn main() {
+    let json = std::env::args().any(|arg| arg == "--json");
+
     let filename = String::from("metro.txt");
     let metro = Metro::new(&filename);
 
@@ -10,14 +12,23 @@ fn main() {
     let arrivals = Metro::get_station(&metro);
     println!();
 
+    let config = RoutingConfig::default();
+
     let start = std::time::Instant::now();
     let mut results = Vec::new();
     for departure in departures {
         for arrival in &arrivals {
-            results.push(Metro::dijkstra(&metro, departure.id, arrival.id));
+            if let Some(result) = Metro::dijkstra(&metro, departure.id, arrival.id, &config) {
+                results.push(result);
+            }
         }
     }
 
+    if results.is_empty() {
+        println!("No route found between the selected stations.");
+        return;
+    }
+
     let mut best = 0;
     for i in 1..results.len() {
         if results[i].time < results[best].time {
@@ -26,7 +37,11 @@ fn main() {
     }
     let end = std::time::Instant::now();
 
-    Metro::print_travel(&metro, &results[best]);
+    if json {
+        println!("{}", Metro::itinerary_json(&metro, &results[best]));
+    } else {
+        Metro::print_travel(&metro, &results[best]);
+    }
 
     let time = end - start;
     println!("Executed in {time:#?}");
@@ -0,0 +1,63 @@
+//! Helpers backing [`Metro::earliest_arrival`]: the per-station index of
+//! timetabled edges, and the lookup of the next usable departure on one of
+//! them.
+//!
+//! [`Metro::earliest_arrival`]: crate::Metro::earliest_arrival
+
+use std::collections::HashMap;
+
+use crate::{Station, StationId, Trip};
+
+/// Builds, for each station, the `(neighbor, trip index)` pairs reachable
+/// directly from it, indexed by station position in `stations`.
+///
+/// A timetabled `Trip` (non-empty `departures`) only records a scheduled
+/// service running `first -> second`, so it's indexed in that direction
+/// alone; treating it as symmetric would let [`Metro::earliest_arrival`]
+/// relax the reverse direction against a departure time that was never
+/// recorded for it. A reverse-direction service needs its own `Trip`
+/// (which GTFS feeds generally provide as a separate trip). An untimed
+/// `Trip` (e.g. a `transfers.txt` walking edge, empty `departures`) has no
+/// direction to get wrong, so it's indexed both ways.
+///
+/// # Arguments
+/// * `stations` - the `Station`s of the network.
+/// * `trips` - the `Trip`s linking those `Station`s together.
+/// * `index` - the `StationId` -> position map built by `build_index`.
+///
+/// [`Metro::earliest_arrival`]: crate::Metro::earliest_arrival
+pub(crate) fn build_timetable(
+    stations: &[Station],
+    trips: &[Trip],
+    index: &HashMap<StationId, usize>
+) -> Vec<Vec<(usize, usize)>> {
+    let mut timetable = vec![Vec::new(); stations.len()];
+
+    for (trip_index, trip) in trips.iter().enumerate() {
+        let first = index[&trip.first];
+        let second = index[&trip.second];
+        timetable[first].push((second, trip_index));
+        if trip.departures.is_empty() {
+            timetable[second].push((first, trip_index));
+        }
+    }
+
+    timetable
+}
+
+/// Returns the earliest entry of a sorted `departures` list that is at or
+/// after `at`, or `at` itself if the edge carries no timetable.
+///
+/// # Arguments
+/// * `departures` - the sorted departure times (seconds since midnight) to
+/// search, or empty if the edge has no known timetable.
+/// * `at` - the time (seconds since midnight) the edge's tail station is
+/// reached at.
+pub(crate) fn next_departure(departures: &[u32], at: u32) -> Option<u32> {
+    if departures.is_empty() {
+        return Some(at);
+    }
+
+    let index = departures.partition_point(|&departure| departure < at);
+    departures.get(index).copied()
+}